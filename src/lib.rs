@@ -2,17 +2,282 @@
 
 use anyhow::{anyhow, ensure, Context, Result};
 use bytes::Bytes;
+use futures::stream::{self, StreamExt};
+use lofty::{Accessor, ItemKey, TaggedFileExt};
 use reqwest::header::{HeaderMap, ACCEPT};
 use reqwest::multipart::{Form, Part};
 use reqwest::{Client, StatusCode, Url};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::fmt::Display;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use uuid::Uuid;
 
 const EMYSOUND_API: &str = "http://localhost:3340/api/v1.1/";
+const DEFAULT_USER: &str = "ADMIN";
+
+/// Policy controlling retries of transient EmySound requests.
+///
+/// Only idempotent/transient failures are retried — connection errors,
+/// timeouts, and HTTP 429/5xx responses — never 4xx validation errors.
+/// Retries use exponential backoff with full jitter: attempt `n` (0-based)
+/// sleeps a random duration in `[0, base_delay * 2^n]`, capped at `max_delay`.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Maximum number of retries after the initial attempt.
+    pub max_retries: u32,
+    /// Base delay used to compute the backoff window.
+    pub base_delay: Duration,
+    /// Upper bound on a single backoff window.
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(10),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Full-jitter backoff window for a 0-based attempt number.
+    fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 1u32.checked_shl(attempt).unwrap_or(u32::MAX);
+        let cap = self.base_delay.saturating_mul(factor).min(self.max_delay);
+        cap.mul_f64(rand::random::<f64>())
+    }
+}
+
+fn is_transient_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+fn is_transient_error(error: &reqwest::Error) -> bool {
+    error.is_timeout() || error.is_connect() || error.is_request()
+}
+
+/// Reusable EmySound API client.
+///
+/// Holds a single [`reqwest::Client`] so connection pooling works across many
+/// calls, together with the endpoint and credentials to use. Build one with
+/// [`EmySoundClient::builder`]; the free [`insert`]/[`query`] functions are
+/// thin wrappers over a client built with the defaults.
+#[derive(Debug, Clone)]
+pub struct EmySoundClient {
+    client: Client,
+    base_url: Url,
+    user: String,
+    password: Option<String>,
+    retry: RetryPolicy,
+}
+
+impl EmySoundClient {
+    /// Starts building a client.
+    pub fn builder() -> EmySoundClientBuilder {
+        EmySoundClientBuilder::default()
+    }
 
+    fn default_client() -> Result<Self> {
+        Self::builder().build()
+    }
+
+    fn authorize(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        request.basic_auth(&self.user, self.password.as_ref())
+    }
+
+    /// Sends a request built by `make`, retrying transient failures according
+    /// to the configured [`RetryPolicy`].
+    ///
+    /// `make` is called once per attempt so a fresh body (e.g. a re-opened
+    /// file stream) is produced each time. Connection errors, timeouts, and
+    /// HTTP 429/5xx responses are retried; other 4xx responses are returned
+    /// as-is.
+    async fn send_with_retry<F, Fut>(&self, target: &str, make: F) -> Result<reqwest::Response>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<reqwest::RequestBuilder>>,
+    {
+        let mut attempt = 0u32;
+        loop {
+            match make().await?.send().await {
+                Ok(res) => {
+                    if attempt < self.retry.max_retries && is_transient_status(res.status()) {
+                        log::warn!(
+                            target: target,
+                            "Transient status {}, retrying (attempt {attempt})",
+                            res.status()
+                        );
+                    } else {
+                        return Ok(res);
+                    }
+                }
+                Err(e) => {
+                    if attempt < self.retry.max_retries && is_transient_error(&e) {
+                        log::warn!(target: target, "Transient error, retrying (attempt {attempt}): {e}");
+                    } else {
+                        return Err(e).context("Sending request to EmySound");
+                    }
+                }
+            }
+
+            tokio::time::sleep(self.retry.backoff(attempt)).await;
+            attempt += 1;
+        }
+    }
+}
+
+/// Builder for [`EmySoundClient`].
 #[derive(Debug, Clone)]
+pub struct EmySoundClientBuilder {
+    base_url: String,
+    user: String,
+    password: Option<String>,
+    accept_invalid_certs: bool,
+    retry: RetryPolicy,
+}
+
+impl Default for EmySoundClientBuilder {
+    fn default() -> Self {
+        Self {
+            base_url: EMYSOUND_API.to_string(),
+            user: DEFAULT_USER.to_string(),
+            password: Some(String::new()),
+            accept_invalid_certs: false,
+            retry: RetryPolicy::default(),
+        }
+    }
+}
+
+impl EmySoundClientBuilder {
+    /// Overrides the API base URL (defaults to the local EmySound instance).
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Sets the HTTP basic-auth credentials.
+    pub fn credentials(mut self, user: impl Into<String>, password: impl Into<String>) -> Self {
+        self.user = user.into();
+        self.password = Some(password.into());
+        self
+    }
+
+    /// Accepts invalid/self-signed TLS certificates. Use only against trusted
+    /// hosts, e.g. a local EmySound behind a self-signed cert.
+    pub fn danger_accept_invalid_certs(mut self, accept: bool) -> Self {
+        self.accept_invalid_certs = accept;
+        self
+    }
+
+    /// Replaces the retry policy (defaults to [`RetryPolicy::default`]).
+    pub fn retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Sets the maximum number of retries for transient failures.
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.retry.max_retries = max_retries;
+        self
+    }
+
+    /// Sets the base delay used for the backoff window.
+    pub fn base_delay(mut self, base_delay: Duration) -> Self {
+        self.retry.base_delay = base_delay;
+        self
+    }
+
+    /// Builds the client, failing if the base URL is invalid or the underlying
+    /// HTTP client cannot be constructed.
+    pub fn build(self) -> Result<EmySoundClient> {
+        // `Url::join` replaces the final path segment when the base lacks a
+        // trailing slash, which would silently drop the API version from a
+        // value like `http://host/api/v1.1`. Normalize it first.
+        let mut base_url = self.base_url;
+        if !base_url.ends_with('/') {
+            base_url.push('/');
+        }
+        let base_url = Url::parse(&base_url)
+            .with_context(|| format!("Invalid base URL {:?}", base_url))?;
+        let client = Client::builder()
+            .danger_accept_invalid_certs(self.accept_invalid_certs)
+            .build()
+            .context("Building HTTP client")?;
+        Ok(EmySoundClient {
+            client,
+            base_url,
+            user: self.user,
+            password: self.password,
+            retry: self.retry,
+        })
+    }
+}
+
+/// Error returned when EmySound responds with a non-success HTTP status.
+///
+/// Carries the [`StatusCode`] so callers can tell a recoverable client
+/// rejection (4xx) apart from a server error (5xx) without parsing the
+/// message text.
+#[derive(Debug)]
+pub struct ApiError {
+    /// HTTP status returned by the server.
+    pub status: StatusCode,
+    /// Response body, used as the error message.
+    pub message: String,
+}
+
+impl Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.status, self.message)
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// Typed result of an API operation for callers that need to distinguish
+/// outcomes without parsing log text.
+///
+/// `Success` carries the operation's content, `Failure` is a recoverable
+/// condition (e.g. no results, an HTTP 4xx rejection), and `Fatal` is an
+/// unrecoverable error (I/O, parsing, or a connection problem).
+#[derive(Debug, Serialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+pub enum ApiOutcome<T> {
+    Success { content: T },
+    Failure { message: String },
+    Fatal { message: String },
+}
+
+impl<T> ApiOutcome<T> {
+    /// Wraps a successful value.
+    pub fn success(content: T) -> Self {
+        Self::Success { content }
+    }
+
+    /// Builds a recoverable failure from a message.
+    pub fn failure(message: impl Into<String>) -> Self {
+        Self::Failure {
+            message: message.into(),
+        }
+    }
+
+    /// Builds a fatal error from a message.
+    pub fn fatal(message: impl Into<String>) -> Self {
+        Self::Fatal {
+            message: message.into(),
+        }
+    }
+
+    /// Whether the outcome represents success.
+    pub fn is_success(&self) -> bool {
+        matches!(self, Self::Success { .. })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
 pub enum MediaSource<'a> {
     File(&'a Path),
     Bytes(&'a str, &'a Bytes),
@@ -30,165 +295,509 @@ impl<'a> Display for MediaSource<'a> {
     }
 }
 
-pub async fn insert(
-    source: MediaSource<'_>,
-    id: Uuid,
-    artist: String,
-    title: String,
-) -> Result<()> {
-    const TARGET: &str = "emysound::insert";
-
-    log::debug!(target: TARGET, "{source}, artist={artist}, title={title}",);
-
-    let file_name = match source {
-        MediaSource::File(path) => path
-            .file_name()
-            .map(|filename| filename.to_string_lossy().to_string())
-            .ok_or_else(|| {
-                log::error!(
-                    target: TARGET,
-                    "Can't extract the filename from path={:?}",
-                    path
-                );
-                anyhow!("Track path is invalid, can't extract the filename")
-            })?,
-        MediaSource::Bytes(file_name, _) => file_name.to_string(),
-    };
-
-    log::debug!(target: TARGET, "Track filename: {}", file_name);
+/// Track tags resolved from an audio file before it is uploaded.
+///
+/// Populated from the file's ID3/Vorbis/MP4 tags so callers can inspect what
+/// will be sent to EmySound. Only the artist and title can be overridden, by
+/// passing them to [`EmySoundClient::insert`]; album, year and cover are always
+/// taken from the file.
+#[derive(Debug, Clone, Default)]
+pub struct TrackMetadata {
+    /// Track artist.
+    pub artist: Option<String>,
+    /// Track title.
+    pub title: Option<String>,
+    /// Album the track belongs to.
+    pub album: Option<String>,
+    /// Release year.
+    pub year: Option<u32>,
+    /// Embedded cover art, if any.
+    pub cover: Option<Bytes>,
+}
 
-    let content = match source {
-        MediaSource::File(path) => {
-            log::debug!(target: TARGET, "Reading track file...");
-            tokio::fs::read(&path).await.context("Reading track file")?
+impl TrackMetadata {
+    /// Reads the tags embedded in an audio file.
+    ///
+    /// The primary tag is used, or the first tag of any kind when the file has
+    /// no primary tag. Any tag that is missing stays `None`, except the title,
+    /// which falls back to the file stem so a track is never uploaded
+    /// completely anonymous.
+    pub fn read(path: &Path) -> Self {
+        const TARGET: &str = "emysound::metadata";
+
+        let mut metadata = Self::default();
+
+        match lofty::read_from_path(path) {
+            Ok(tagged) => {
+                if let Some(tag) = tagged.primary_tag().or_else(|| tagged.first_tag()) {
+                    metadata.artist = tag.artist().map(|v| v.to_string());
+                    metadata.title = tag.title().map(|v| v.to_string());
+                    metadata.album = tag.album().map(|v| v.to_string());
+                    metadata.year = tag
+                        .get_string(&ItemKey::Year)
+                        .and_then(|v| v.parse().ok())
+                        .or_else(|| tag.year());
+                    metadata.cover = tag
+                        .pictures()
+                        .first()
+                        .map(|picture| Bytes::copy_from_slice(picture.data()));
+                }
+            }
+            Err(e) => {
+                log::debug!(target: TARGET, "No tags read from {:?}: {e}", path);
+            }
         }
-        MediaSource::Bytes(_, bytes) => bytes.to_vec(),
-    };
 
-    let content_length = content.len();
+        if metadata.title.is_none() {
+            metadata.title = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string());
+        }
 
-    let headers = {
-        let mut h = HeaderMap::new();
-        h.insert(ACCEPT, "application/json".parse()?);
-        h
-    };
+        metadata
+    }
+}
 
-    let form = Form::new()
-        .text("Id", id.to_string())
-        .text("Artist", artist)
-        .text("Title", title)
-        .text("MediaType", "Audio")
-        .part(
-            "file",
-            Part::stream_with_length(content, content_length as u64)
-                .file_name(file_name)
-                .mime_str("application/octet-stream")
-                .context("Preparing form content")?,
-        );
+impl EmySoundClient {
+    pub async fn insert(
+        &self,
+        source: MediaSource<'_>,
+        artist: Option<String>,
+        title: Option<String>,
+    ) -> Result<Uuid> {
+        const TARGET: &str = "emysound::insert";
+
+        log::debug!(target: TARGET, "{source}, artist={artist:?}, title={title:?}",);
+
+        let file_name = match source {
+            MediaSource::File(path) => path
+                .file_name()
+                .map(|filename| filename.to_string_lossy().to_string())
+                .ok_or_else(|| {
+                    log::error!(
+                        target: TARGET,
+                        "Can't extract the filename from path={:?}",
+                        path
+                    );
+                    anyhow!("Track path is invalid, can't extract the filename")
+                })?,
+            MediaSource::Bytes(file_name, _) => file_name.to_string(),
+        };
+
+        log::debug!(target: TARGET, "Track filename: {}", file_name);
+
+        // Fill in any missing artist/title (and extra tags) from the file itself.
+        let tags = match source {
+            MediaSource::File(path) if artist.is_none() || title.is_none() => {
+                log::debug!(target: TARGET, "Reading track tags...");
+                TrackMetadata::read(path)
+            }
+            _ => TrackMetadata::default(),
+        };
+
+        let artist = artist.or(tags.artist);
+        let title = title.or(tags.title);
+        let album = tags.album;
+        let year = tags.year;
+        let cover = tags.cover;
+
+        let id = Uuid::new_v4();
+        let url = self.base_url.join("Tracks")?;
+
+        log::debug!("Sending request to EmySound");
+        let res = self
+            .send_with_retry(TARGET, || {
+                let url = url.clone();
+                let file_name = file_name.clone();
+                let artist = artist.clone();
+                let title = title.clone();
+                let album = album.clone();
+                let cover = cover.clone();
+                async move {
+                    let mut headers = HeaderMap::new();
+                    headers.insert(ACCEPT, "application/json".parse()?);
+
+                    let mut form = Form::new().text("Id", id.to_string());
+                    if let Some(artist) = artist {
+                        form = form.text("Artist", artist);
+                    }
+                    if let Some(title) = title {
+                        form = form.text("Title", title);
+                    }
+                    if let Some(album) = album {
+                        form = form.text("Album", album);
+                    }
+                    if let Some(year) = year {
+                        form = form.text("Year", year.to_string());
+                    }
+                    if let Some(cover) = cover {
+                        let cover_length = cover.len();
+                        form = form.part(
+                            "cover",
+                            Part::stream_with_length(cover, cover_length as u64)
+                                .mime_str("application/octet-stream")
+                                .context("Preparing cover art")?,
+                        );
+                    }
+                    let form = form
+                        .text("MediaType", "Audio")
+                        .part("file", file_part(source, file_name).await?);
+
+                    Ok(self
+                        .authorize(self.client.post(url))
+                        .headers(headers)
+                        .multipart(form))
+                }
+            })
+            .await?;
+
+        let status = res.status();
+
+        match status {
+            StatusCode::OK => Ok(id),
+            _ => {
+                let text = res.text().await?;
+                log::error!(target: TARGET, "Failed to insert track {status} {text}");
+                Err(ApiError { status, message: text }.into()).context("Failed to insert track")
+            }
+        }
+    }
 
-    log::debug!("Sending request to EmySound");
-    let url: Url = Url::parse(EMYSOUND_API)?;
-    let url = url.join("Tracks")?;
-    let res = Client::new()
-        .post(url)
-        .basic_auth("ADMIN", Some(""))
-        .headers(headers)
-        .multipart(form)
-        .send()
-        .await?;
-
-    let status = res.status();
-
-    match status {
-        StatusCode::OK => Ok(()),
-        _ => {
-            let text = res.text().await?;
-            log::error!(target: TARGET, "Failed to insert track {status} {text}");
-            Err(anyhow!("Failed to insert track {status} {text}"))
+    /// Inserts every audio file in `dir` into EmySound, running up to
+    /// `concurrency` uploads at a time.
+    ///
+    /// Non-audio files are skipped and reported in [`InsertSummary::skipped`]. A
+    /// single failing track does not abort the batch — its error is collected in
+    /// [`InsertSummary::results`] alongside the successes. Artist and title are
+    /// always resolved from each file's tags.
+    pub async fn insert_many(&self, dir: &Path, concurrency: usize) -> Result<InsertSummary> {
+        const TARGET: &str = "emysound::insert_many";
+
+        let concurrency = concurrency.max(1);
+        log::debug!(target: TARGET, "Ingesting {:?} with concurrency={concurrency}", dir);
+
+        let mut paths = Vec::new();
+        let mut skipped = Vec::new();
+        let mut entries = tokio::fs::read_dir(dir)
+            .await
+            .with_context(|| format!("Reading directory {:?}", dir))?;
+        while let Some(entry) = entries.next_entry().await.context("Reading directory entry")? {
+            let path = entry.path();
+            if !path.is_file() {
+                continue;
+            }
+            if is_audio_file(&path) {
+                paths.push(path);
+            } else {
+                skipped.push(path);
+            }
         }
+
+        let results = stream::iter(paths)
+            .map(|path| async move {
+                let result = self.insert(MediaSource::File(path.as_path()), None, None).await;
+                (path, result)
+            })
+            .buffer_unordered(concurrency)
+            .collect::<Vec<_>>()
+            .await;
+
+        Ok(InsertSummary { results, skipped })
     }
 }
 
-pub async fn query(source: MediaSource<'_>, min_confidence: f32) -> Result<Vec<QueryResult>> {
-    ensure!(
-        min_confidence >= 0f32 && min_confidence <= 1f32,
-        "Min confidence must be between 0 and 1"
-    );
-
-    const TARGET: &str = "emysound::query";
-    log::debug!(target: TARGET, "{source}",);
-
-    let file_name = match source {
-        MediaSource::File(path) => path
-            .file_name()
-            .map(|filename| filename.to_string_lossy().to_string())
-            .ok_or_else(|| {
-                log::error!(
-                    target: TARGET,
-                    "Can't extract the filename from path={:?}",
-                    path
-                );
-                anyhow!("Track path is invalid, can't extract the filename")
-            })?,
-        MediaSource::Bytes(file_name, _) => file_name.to_string(),
-    };
+/// Outcome of an [`insert_many`] batch.
+#[derive(Debug, Default)]
+pub struct InsertSummary {
+    /// Per-file insert result, in completion order.
+    pub results: Vec<(PathBuf, Result<Uuid>)>,
+    /// Files that were not audio and therefore never uploaded.
+    pub skipped: Vec<PathBuf>,
+}
 
-    log::debug!(target: TARGET, "Track filename: {}", file_name);
+impl InsertSummary {
+    /// Number of tracks that were inserted successfully.
+    pub fn succeeded(&self) -> usize {
+        self.results.iter().filter(|(_, r)| r.is_ok()).count()
+    }
 
-    let content = match source {
+    /// Number of tracks that failed to insert.
+    pub fn failed(&self) -> usize {
+        self.results.iter().filter(|(_, r)| r.is_err()).count()
+    }
+}
+
+/// File extensions treated as audio by [`insert_many`].
+const AUDIO_EXTENSIONS: &[&str] = &[
+    "mp3", "flac", "wav", "ogg", "oga", "opus", "m4a", "m4b", "aac", "wma", "aiff", "aif",
+];
+
+/// Builds the multipart `file` part for a media source.
+///
+/// For [`MediaSource::File`] the file is streamed straight from disk so peak
+/// memory stays flat regardless of track size; the length is taken from the
+/// file metadata. [`MediaSource::Bytes`] keeps its in-memory payload.
+async fn file_part(source: MediaSource<'_>, file_name: String) -> Result<Part> {
+    let part = match source {
         MediaSource::File(path) => {
-            log::debug!(target: TARGET, "Reading track file...");
-            tokio::fs::read(&path).await.context("Reading track file")?
+            let file = tokio::fs::File::open(path)
+                .await
+                .context("Opening track file")?;
+            let length = file
+                .metadata()
+                .await
+                .context("Reading track file metadata")?
+                .len();
+            let stream = tokio_util::io::ReaderStream::new(file);
+            Part::stream_with_length(reqwest::Body::wrap_stream(stream), length)
+        }
+        MediaSource::Bytes(_, bytes) => {
+            Part::stream_with_length(bytes.to_vec(), bytes.len() as u64)
         }
-        MediaSource::Bytes(_, bytes) => bytes.to_vec(),
     };
 
-    let content_length = content.len();
+    part.file_name(file_name)
+        .mime_str("application/octet-stream")
+        .context("Preparing form content")
+}
 
-    let headers = {
-        let mut h = HeaderMap::new();
-        h.insert(ACCEPT, "application/json".parse()?);
-        h
-    };
+fn is_audio_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| AUDIO_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+impl EmySoundClient {
+    pub async fn query(
+        &self,
+        source: MediaSource<'_>,
+        min_confidence: f32,
+    ) -> Result<Vec<QueryResult>> {
+        ensure!(
+            min_confidence >= 0f32 && min_confidence <= 1f32,
+            "Min confidence must be between 0 and 1"
+        );
+
+        const TARGET: &str = "emysound::query";
+        log::debug!(target: TARGET, "{source}",);
+
+        let file_name = match source {
+            MediaSource::File(path) => path
+                .file_name()
+                .map(|filename| filename.to_string_lossy().to_string())
+                .ok_or_else(|| {
+                    log::error!(
+                        target: TARGET,
+                        "Can't extract the filename from path={:?}",
+                        path
+                    );
+                    anyhow!("Track path is invalid, can't extract the filename")
+                })?,
+            MediaSource::Bytes(file_name, _) => file_name.to_string(),
+        };
+
+        log::debug!(target: TARGET, "Track filename: {}", file_name);
+
+        log::debug!(target: TARGET, "Sending request to EmySound");
+
+        let url = self.base_url.join("Query")?;
+
+        let res = self
+            .send_with_retry(TARGET, || {
+                let url = url.clone();
+                let file_name = file_name.clone();
+                let min_coverage = min_confidence.to_string();
+                async move {
+                    let mut headers = HeaderMap::new();
+                    headers.insert(ACCEPT, "application/json".parse()?);
+
+                    let form = Form::new().part("file", file_part(source, file_name).await?);
+
+                    Ok(self
+                        .authorize(self.client.post(url))
+                        .headers(headers)
+                        .query(&[
+                            ("mediaType", "Audio"),
+                            ("minCoverage", &min_coverage),
+                            ("registerMatches", "true"),
+                        ])
+                        .multipart(form))
+                }
+            })
+            .await?;
+
+        let status = res.status();
+
+        match status {
+            StatusCode::OK => res.json().await.context("Decode response body failed"),
+            _ => {
+                let text = res.text().await?;
+                log::error!(target: TARGET, "Failed to query track {status} {text}");
+                Err(ApiError { status, message: text }.into()).context("Failed to query track")
+            }
+        }
+    }
+
+    /// Lists every track stored in the database.
+    pub async fn list_tracks(&self) -> Result<Vec<TrackInfo>> {
+        const TARGET: &str = "emysound::list_tracks";
+        log::debug!(target: TARGET, "Listing tracks");
+
+        let url = self.base_url.join("Tracks")?;
+        let res = self
+            .send_with_retry(TARGET, || {
+                let url = url.clone();
+                async move { Ok(self.authorize(self.client.get(url)).header(ACCEPT, "application/json")) }
+            })
+            .await?;
+
+        let status = res.status();
+        match status {
+            StatusCode::OK => res.json().await.context("Decode response body failed"),
+            _ => {
+                let text = res.text().await?;
+                log::error!(target: TARGET, "Failed to list tracks {status} {text}");
+                Err(ApiError { status, message: text }.into()).context("Failed to list tracks")
+            }
+        }
+    }
+
+    /// Fetches a single track by id, returning `None` when it does not exist.
+    pub async fn get_track(&self, id: Uuid) -> Result<Option<TrackInfo>> {
+        const TARGET: &str = "emysound::get_track";
+        log::debug!(target: TARGET, "Getting track {id}");
+
+        let url = self.base_url.join(&format!("Tracks/{id}"))?;
+        let res = self
+            .send_with_retry(TARGET, || {
+                let url = url.clone();
+                async move { Ok(self.authorize(self.client.get(url)).header(ACCEPT, "application/json")) }
+            })
+            .await?;
+
+        let status = res.status();
+        match status {
+            StatusCode::OK => res.json().await.map(Some).context("Decode response body failed"),
+            StatusCode::NOT_FOUND => Ok(None),
+            _ => {
+                let text = res.text().await?;
+                log::error!(target: TARGET, "Failed to get track {status} {text}");
+                Err(ApiError { status, message: text }.into()).context("Failed to get track")
+            }
+        }
+    }
 
-    let form = Form::new().part(
-        "file",
-        Part::stream_with_length(content, content_length as u64)
-            .file_name(file_name)
-            .mime_str("application/octet-stream")
-            .context("Preparing form content")?,
-    );
-
-    let client = reqwest::Client::new();
-
-    log::debug!(target: TARGET, "Sending request to EmySound");
-
-    let url: Url = Url::parse(EMYSOUND_API)?;
-    let url = url.join("Query")?;
-
-    let res = client
-        .post(url)
-        .basic_auth("ADMIN", Some(""))
-        .headers(headers)
-        .query(&[
-            ("mediaType", "Audio"),
-            ("minCoverage", &min_confidence.to_string()),
-            ("registerMatches", "true"),
-        ])
-        .multipart(form)
-        .send()
-        .await?;
-
-    let status = res.status();
-
-    match status {
-        StatusCode::OK => res.json().await.context("Decode response body failed"),
-        _ => {
-            let text = res.text().await?;
-            log::error!(target: TARGET, "Failed to query track {status} {text}");
-            Err(anyhow!("Failed to query track {status} {text}"))
+    /// Deletes a track by id.
+    pub async fn delete_track(&self, id: Uuid) -> Result<()> {
+        const TARGET: &str = "emysound::delete_track";
+        log::debug!(target: TARGET, "Deleting track {id}");
+
+        let url = self.base_url.join(&format!("Tracks/{id}"))?;
+        let res = self
+            .send_with_retry(TARGET, || {
+                let url = url.clone();
+                async move { Ok(self.authorize(self.client.delete(url))) }
+            })
+            .await?;
+
+        let status = res.status();
+        match status {
+            StatusCode::OK | StatusCode::NO_CONTENT => Ok(()),
+            _ => {
+                let text = res.text().await?;
+                log::error!(target: TARGET, "Failed to delete track {status} {text}");
+                Err(ApiError { status, message: text }.into()).context("Failed to delete track")
+            }
         }
     }
+
+    /// Fetches the registered matches for a previous query match id.
+    pub async fn get_matches(&self, query_match_id: &str) -> Result<Vec<QueryResult>> {
+        const TARGET: &str = "emysound::get_matches";
+        log::debug!(target: TARGET, "Getting matches for {query_match_id}");
+
+        let url = self.base_url.join(&format!("Matches/{query_match_id}"))?;
+        let res = self
+            .send_with_retry(TARGET, || {
+                let url = url.clone();
+                async move { Ok(self.authorize(self.client.get(url)).header(ACCEPT, "application/json")) }
+            })
+            .await?;
+
+        let status = res.status();
+        match status {
+            StatusCode::OK => res.json().await.context("Decode response body failed"),
+            _ => {
+                let text = res.text().await?;
+                log::error!(target: TARGET, "Failed to get matches {status} {text}");
+                Err(ApiError { status, message: text }.into()).context("Failed to get matches")
+            }
+        }
+    }
+}
+
+/// Inserts a track using a client built with the default configuration.
+///
+/// Thin wrapper over [`EmySoundClient::insert`] kept for backward
+/// compatibility; prefer building an [`EmySoundClient`] to reuse the
+/// underlying connection pool across calls.
+pub async fn insert(
+    source: MediaSource<'_>,
+    artist: Option<String>,
+    title: Option<String>,
+) -> Result<Uuid> {
+    EmySoundClient::default_client()?
+        .insert(source, artist, title)
+        .await
+}
+
+/// Ingests a directory using a client built with the default configuration.
+///
+/// Thin wrapper over [`EmySoundClient::insert_many`].
+pub async fn insert_many(dir: &Path, concurrency: usize) -> Result<InsertSummary> {
+    EmySoundClient::default_client()?
+        .insert_many(dir, concurrency)
+        .await
+}
+
+/// Queries the database using a client built with the default configuration.
+///
+/// Thin wrapper over [`EmySoundClient::query`].
+pub async fn query(source: MediaSource<'_>, min_confidence: f32) -> Result<Vec<QueryResult>> {
+    EmySoundClient::default_client()?
+        .query(source, min_confidence)
+        .await
+}
+
+/// Lists tracks using a client built with the default configuration.
+///
+/// Thin wrapper over [`EmySoundClient::list_tracks`].
+pub async fn list_tracks() -> Result<Vec<TrackInfo>> {
+    EmySoundClient::default_client()?.list_tracks().await
+}
+
+/// Fetches a track by id using a client built with the default configuration.
+///
+/// Thin wrapper over [`EmySoundClient::get_track`].
+pub async fn get_track(id: Uuid) -> Result<Option<TrackInfo>> {
+    EmySoundClient::default_client()?.get_track(id).await
+}
+
+/// Deletes a track by id using a client built with the default configuration.
+///
+/// Thin wrapper over [`EmySoundClient::delete_track`].
+pub async fn delete_track(id: Uuid) -> Result<()> {
+    EmySoundClient::default_client()?.delete_track(id).await
+}
+
+/// Fetches query matches using a client built with the default configuration.
+///
+/// Thin wrapper over [`EmySoundClient::get_matches`].
+pub async fn get_matches(query_match_id: &str) -> Result<Vec<QueryResult>> {
+    EmySoundClient::default_client()?
+        .get_matches(query_match_id)
+        .await
 }
 
 #[derive(Debug, Deserialize)]
@@ -267,3 +876,79 @@ pub struct Gap {
     /// Gets length in seconds calculated by the difference: End - Start.
     pub length_in_seconds: f32,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_falls_back_to_file_stem_for_title() {
+        // A path that can't be opened yields no tags, so the title should come
+        // from the filename stem and the remaining fields stay unset.
+        let metadata = TrackMetadata::read(Path::new("/no/such/dir/Great Song.mp3"));
+        assert_eq!(metadata.title.as_deref(), Some("Great Song"));
+        assert_eq!(metadata.artist, None);
+        assert_eq!(metadata.album, None);
+        assert_eq!(metadata.year, None);
+    }
+
+    #[test]
+    fn audio_files_are_detected_case_insensitively() {
+        assert!(is_audio_file(Path::new("song.mp3")));
+        assert!(is_audio_file(Path::new("song.FLAC")));
+        assert!(!is_audio_file(Path::new("cover.jpg")));
+        assert!(!is_audio_file(Path::new("no_extension")));
+    }
+
+    #[test]
+    fn insert_summary_counts_successes_and_failures() {
+        let summary = InsertSummary {
+            results: vec![
+                (PathBuf::from("a.mp3"), Ok(Uuid::nil())),
+                (PathBuf::from("b.mp3"), Err(anyhow!("boom"))),
+                (PathBuf::from("c.mp3"), Ok(Uuid::nil())),
+            ],
+            skipped: vec![PathBuf::from("notes.txt")],
+        };
+        assert_eq!(summary.succeeded(), 2);
+        assert_eq!(summary.failed(), 1);
+        assert_eq!(summary.skipped.len(), 1);
+    }
+
+    #[test]
+    fn backoff_stays_within_the_jitter_window() {
+        let policy = RetryPolicy {
+            max_retries: 5,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(1),
+        };
+        // attempt 0: window [0, 100ms]; attempt 3: window [0, 800ms].
+        for _ in 0..1000 {
+            assert!(policy.backoff(0) <= Duration::from_millis(100));
+            assert!(policy.backoff(3) <= Duration::from_millis(800));
+        }
+    }
+
+    #[test]
+    fn backoff_is_capped_at_max_delay() {
+        let policy = RetryPolicy {
+            max_retries: 10,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(5),
+        };
+        // A huge attempt would overflow the shift; the window must still cap.
+        for _ in 0..1000 {
+            assert!(policy.backoff(40) <= Duration::from_secs(5));
+        }
+    }
+
+    #[test]
+    fn transient_statuses_are_retried() {
+        assert!(is_transient_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_transient_status(StatusCode::INTERNAL_SERVER_ERROR));
+        assert!(is_transient_status(StatusCode::BAD_GATEWAY));
+        assert!(!is_transient_status(StatusCode::BAD_REQUEST));
+        assert!(!is_transient_status(StatusCode::NOT_FOUND));
+        assert!(!is_transient_status(StatusCode::OK));
+    }
+}
@@ -1,31 +1,58 @@
 #![allow(dead_code)]
 
-use anyhow::{anyhow, Context};
 use clap::{Parser, Subcommand};
 use log::LevelFilter;
+use serde::Serialize;
 use std::path::PathBuf;
+use uuid::Uuid;
 
-use emycloud_client_rs::{insert, query, MediaSource};
+use emycloud_client_rs::{
+    delete_track, get_matches, insert, insert_many, list_tracks, query, ApiError, ApiOutcome,
+    MediaSource,
+};
 
 #[derive(Debug, Subcommand)]
 enum Commands {
     /// Add tracks to the database.
     Insert {
         /// Track filename.
-        #[clap(short, long, parse(from_os_str))]
-        file: PathBuf,
-        /// Track artist.
+        #[clap(short, long, parse(from_os_str), required_unless_present = "dir")]
+        file: Option<PathBuf>,
+        /// Ingest every audio file in this directory instead of a single file.
+        #[clap(short, long, parse(from_os_str), conflicts_with = "file")]
+        dir: Option<PathBuf>,
+        /// Number of concurrent uploads when ingesting a directory.
+        #[clap(short, long, default_value_t = 4)]
+        concurrency: usize,
+        /// Track artist. Read from the file's tags when omitted.
         #[clap(short, long)]
-        artist: String,
-        /// Track title.
+        artist: Option<String>,
+        /// Track title. Read from the file's tags (or the filename) when omitted.
         #[clap(short, long)]
-        title: String,
+        title: Option<String>,
     },
     /// Query database for similar tracks.
     Query {
         /// Track filename.
         #[clap(short, long, parse(from_os_str))]
         file: PathBuf,
+        /// Minimum match coverage, between 0 and 1.
+        #[clap(short, long, default_value_t = 0.0)]
+        min_confidence: f32,
+    },
+    /// List tracks stored in the database.
+    List,
+    /// Delete a track by id.
+    Delete {
+        /// Track identifier.
+        #[clap(short, long)]
+        id: Uuid,
+    },
+    /// Show registered matches for a query match id.
+    Matches {
+        /// Query match identifier.
+        #[clap(short, long)]
+        id: String,
     },
 }
 
@@ -34,10 +61,68 @@ struct Args {
     /// Show only match scores and errors.
     #[clap(short, long)]
     quiet: bool,
+    /// Emit the outcome as JSON instead of human-readable text.
+    #[clap(long)]
+    json: bool,
     #[clap(subcommand)]
     command: Commands,
 }
 
+#[derive(Debug, Serialize)]
+struct InsertReport {
+    id: String,
+}
+
+#[derive(Debug, Serialize)]
+struct BatchReport {
+    inserted: usize,
+    failed: usize,
+    skipped: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct QueryMatch {
+    score: f32,
+    track_id: String,
+}
+
+#[derive(Debug, Serialize)]
+struct TrackRow {
+    id: String,
+    artist: Option<String>,
+    title: Option<String>,
+    length: f32,
+}
+
+/// Classifies a client error into a recoverable [`ApiOutcome::Failure`] for
+/// HTTP 4xx rejections, or a [`ApiOutcome::Fatal`] for everything else
+/// (5xx, connection, I/O, and parse errors).
+fn classify<T>(error: anyhow::Error) -> ApiOutcome<T> {
+    match error.downcast_ref::<ApiError>() {
+        Some(api) if api.status.is_client_error() => ApiOutcome::failure(error.to_string()),
+        _ => ApiOutcome::fatal(error.to_string()),
+    }
+}
+
+/// Prints an outcome, either as JSON or in the human-readable form produced by
+/// `human`, and returns whether it was a success.
+fn emit<T: Serialize>(outcome: &ApiOutcome<T>, json: bool, human: impl FnOnce(&T)) -> bool {
+    if json {
+        match serde_json::to_string(outcome) {
+            Ok(text) => println!("{text}"),
+            Err(e) => eprintln!("Failed to serialize outcome: {e}"),
+        }
+    } else {
+        match outcome {
+            ApiOutcome::Success { content } => human(content),
+            ApiOutcome::Failure { message } | ApiOutcome::Fatal { message } => {
+                eprintln!("{message}")
+            }
+        }
+    }
+    outcome.is_success()
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let args = Args::parse();
@@ -51,59 +136,168 @@ async fn main() -> anyhow::Result<()> {
             .open("emysound-client.log")?,
     )?;
 
-    match args.command {
+    let success = match args.command {
         Commands::Insert {
             file,
+            dir,
+            concurrency,
             artist,
             title,
         } => {
-            match insert(MediaSource::File(file.as_path()), artist, title)
-                .await
-                .context("Failed to insert track {file}")
-            {
-                Ok(id) => {
-                    println!("{id}");
-                    Ok(())
+            if let Some(dir) = dir {
+                let outcome = match insert_many(dir.as_path(), concurrency).await {
+                    Ok(summary) => {
+                        for path in &summary.skipped {
+                            log::info!("Skipped non-audio file {:?}", path);
+                        }
+                        ApiOutcome::success(BatchReport {
+                            inserted: summary.succeeded(),
+                            failed: summary.failed(),
+                            skipped: summary.skipped.len(),
+                        })
+                    }
+                    Err(e) => {
+                        log::error!("Failed to ingest directory {:?}: {e}", dir);
+                        classify(e)
+                    }
+                };
+                emit(&outcome, args.json, |r| {
+                    println!(
+                        "inserted {}, failed {}, skipped {}",
+                        r.inserted, r.failed, r.skipped
+                    )
+                })
+            } else {
+                let file = file.expect("clap guarantees file is present without --dir");
+                let outcome = match insert(MediaSource::File(file.as_path()), artist, title).await {
+                    Ok(id) => ApiOutcome::success(InsertReport { id: id.to_string() }),
+                    Err(e) => {
+                        log::error!("Failed to insert track {:?}: {e}", file);
+                        classify(e)
+                    }
+                };
+                emit(&outcome, args.json, |r| println!("{}", r.id))
+            }
+        }
+
+        Commands::Query {
+            file,
+            min_confidence,
+        } => {
+            let outcome = match query(MediaSource::File(file.as_path()), min_confidence).await {
+                Ok(results) => {
+                    log::debug!("{results:?}");
+                    if results.is_empty() {
+                        log::info!("No results.");
+                        ApiOutcome::failure("No results")
+                    } else {
+                        let matches = results
+                            .into_iter()
+                            .map(|result| QueryMatch {
+                                score: result
+                                    .audio
+                                    .as_ref()
+                                    .and_then(|m| m.coverage.query_coverage)
+                                    .unwrap_or_default(),
+                                track_id: result.track.id,
+                            })
+                            .collect::<Vec<_>>();
+                        ApiOutcome::success(matches)
+                    }
                 }
                 Err(e) => {
-                    log::error!("Failed to insert track {e}");
-                    Err(e)
+                    log::error!("Failed to query track {:?}: {e}", file);
+                    classify(e)
                 }
-            }
+            };
+            emit(&outcome, args.json, |matches| {
+                for m in matches {
+                    println!("{:0.3} {}", m.score, m.track_id);
+                }
+            })
+        }
+
+        Commands::List => {
+            let outcome = match list_tracks().await {
+                Ok(tracks) => {
+                    let rows = tracks
+                        .into_iter()
+                        .map(|track| TrackRow {
+                            id: track.id,
+                            artist: track.artist,
+                            title: track.title,
+                            length: track.length,
+                        })
+                        .collect::<Vec<_>>();
+                    ApiOutcome::success(rows)
+                }
+                Err(e) => {
+                    log::error!("Failed to list tracks: {e}");
+                    classify(e)
+                }
+            };
+            emit(&outcome, args.json, |rows| {
+                for row in rows {
+                    println!(
+                        "{}\t{}\t{}\t{:0.1}",
+                        row.id,
+                        row.artist.as_deref().unwrap_or("-"),
+                        row.title.as_deref().unwrap_or("-"),
+                        row.length
+                    );
+                }
+            })
+        }
+
+        Commands::Delete { id } => {
+            let outcome = match delete_track(id).await {
+                Ok(()) => ApiOutcome::success(id.to_string()),
+                Err(e) => {
+                    log::error!("Failed to delete track {id}: {e}");
+                    classify(e)
+                }
+            };
+            emit(&outcome, args.json, |id| println!("{id}"))
         }
 
-        Commands::Query { file } => {
-            match query(MediaSource::File(file.as_path()))
-                .await
-                .context(format!("Failed to query track {:?}", file))
-            {
+        Commands::Matches { id } => {
+            let outcome = match get_matches(&id).await {
                 Ok(results) => {
                     log::debug!("{results:?}");
-
-                    // results.iter().sort
-                    for result in &results {
-                        println!(
-                            "{:0.3} {}",
-                            result
-                                .audio
-                                .as_ref()
-                                .and_then(|m| m.coverage.query_coverage)
-                                .unwrap_or_default(),
-                            result.track.id
-                        )
-                    }
                     if results.is_empty() {
-                        log::info!("No results.");
-                        Err(anyhow!("No results"))
+                        log::info!("No matches.");
+                        ApiOutcome::failure("No matches")
                     } else {
-                        Ok(())
+                        let matches = results
+                            .into_iter()
+                            .map(|result| QueryMatch {
+                                score: result
+                                    .audio
+                                    .as_ref()
+                                    .and_then(|m| m.coverage.query_coverage)
+                                    .unwrap_or_default(),
+                                track_id: result.track.id,
+                            })
+                            .collect::<Vec<_>>();
+                        ApiOutcome::success(matches)
                     }
                 }
                 Err(e) => {
-                    log::error!("{e}");
-                    Err(e)
+                    log::error!("Failed to get matches for {id}: {e}");
+                    classify(e)
                 }
-            }
+            };
+            emit(&outcome, args.json, |matches| {
+                for m in matches {
+                    println!("{:0.3} {}", m.score, m.track_id);
+                }
+            })
         }
+    };
+
+    if !success {
+        std::process::exit(1);
     }
+
+    Ok(())
 }